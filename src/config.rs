@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+/// Tuning knobs for a single browser query.
+///
+/// SSRP runs over UDP, so a query can simply go unanswered. `QueryConfig`
+/// controls how long to wait for a reply after each send, and how many
+/// additional times to re-send the request before giving up.
+#[derive(Debug, Clone, Copy)]
+pub struct QueryConfig {
+    /// How long to wait for a response after each send, before retrying
+    /// or giving up.
+    pub timeout: Duration,
+
+    /// How many additional times to re-send the request after the first
+    /// timeout, before surfacing `BrowserError::Timeout`.
+    pub retries: u32
+}
+
+impl QueryConfig {
+    /// Creates a `QueryConfig` with the given per-attempt `timeout` and
+    /// number of `retries`.
+    pub fn new(timeout: Duration, retries: u32) -> Self {
+        Self { timeout, retries }
+    }
+
+    /// The total number of attempts a query will make: the initial send
+    /// plus `retries` re-sends.
+    pub fn attempts(&self) -> u32 {
+        self.retries + 1
+    }
+}
+
+impl Default for QueryConfig {
+    /// Waits 1 second per attempt and retries twice, for a total budget
+    /// of up to 3 seconds.
+    fn default() -> Self {
+        Self::new(Duration::from_secs(1), 2)
+    }
+}
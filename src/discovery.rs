@@ -0,0 +1,198 @@
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::time::{ Duration, Instant };
+
+use futures::Stream;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::error::BrowserError;
+use crate::socket::BrowserSocket;
+
+/// A single SQL Server instance discovered via a broadcast query,
+/// identified by its `ServerName` and `InstanceName`
+/// [`BrowserProtocolField`](crate::BrowserProtocolField)s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredInstance {
+    /// The address the reply was received from.
+    pub addr: SocketAddr,
+
+    /// The value of the `ServerName` field.
+    pub server_name: String,
+
+    /// The value of the `InstanceName` field.
+    pub instance_name: String
+}
+
+/// Broadcasts a `CLNT_BCAST_EX` request to `addr` and returns a stream
+/// that yields each distinct instance as its reply arrives.
+///
+/// Replies are deduplicated by source address and instance name, so a
+/// server that answers more than once within the collection window is
+/// only yielded once. The stream completes once `deadline` has elapsed
+/// since the broadcast was sent, rather than after a single receive, so
+/// callers (GUIs, CLIs) can show servers as they appear instead of
+/// blocking for the whole window. If binding, sending or receiving
+/// fails, the error is yielded as the stream's last item and the stream
+/// completes early, without waiting for the deadline.
+///
+/// Binds a fresh `S` for the broadcast; use [`discover_with`] to reuse
+/// an existing socket, e.g. a pre-loaded `MockSocket` in tests.
+pub fn discover<S>(addr: SocketAddr, deadline: Duration) -> impl Stream<Item = Result<DiscoveredInstance, BrowserError<S::Error>>>
+where
+    S: BrowserSocket + Send + Sync + 'static,
+    S::Error: Send + 'static
+{
+    let (tx, rx) = mpsc::channel(16);
+
+    tokio::spawn(async move {
+        let socket = match S::bind(SocketAddr::from(([0, 0, 0, 0], 0))).await {
+            Ok(socket) => socket,
+            Err(err) => {
+                let _ = tx.send(Err(BrowserError::BindFailed(err))).await;
+                return;
+            }
+        };
+
+        if let Err(err) = collect(&socket, addr, deadline, &tx).await {
+            let _ = tx.send(Err(err)).await;
+        }
+    });
+
+    ReceiverStream::new(rx)
+}
+
+/// Like [`discover`], but against an already-constructed `socket`
+/// instead of binding a new one.
+pub fn discover_with<S>(socket: S, addr: SocketAddr, deadline: Duration) -> impl Stream<Item = Result<DiscoveredInstance, BrowserError<S::Error>>>
+where
+    S: BrowserSocket + Send + Sync + 'static,
+    S::Error: Send + 'static
+{
+    let (tx, rx) = mpsc::channel(16);
+
+    tokio::spawn(async move {
+        if let Err(err) = collect(&socket, addr, deadline, &tx).await {
+            let _ = tx.send(Err(err)).await;
+        }
+    });
+
+    ReceiverStream::new(rx)
+}
+
+async fn collect<S>(
+    socket: &S,
+    addr: SocketAddr,
+    deadline: Duration,
+    tx: &mpsc::Sender<Result<DiscoveredInstance, BrowserError<S::Error>>>
+) -> Result<(), BrowserError<S::Error>>
+where
+    S: BrowserSocket
+{
+    socket.set_broadcast(true).map_err(BrowserError::SetBroadcastFailed)?;
+    socket.send_to(&[0x02], addr).await.map_err(|e| BrowserError::SendFailed(addr, e))?;
+
+    let started = Instant::now();
+    let mut seen = HashSet::new();
+    let mut buf = [0u8; 4096];
+
+    while let Some(remaining) = deadline.checked_sub(started.elapsed()) {
+        let received = match tokio::time::timeout(remaining, socket.recv_from(&mut buf)).await {
+            Ok(result) => result,
+            Err(_elapsed) => break
+        };
+
+        let (len, from) = match received {
+            Ok(received) => received,
+            Err(err) => {
+                // A broken or exhausted socket won't recover on its own;
+                // retrying it in a loop would just busy-spin until the
+                // deadline. Report the error once and stop receiving.
+                let _ = tx.send(Err(BrowserError::ReceiveFailed(err))).await;
+                return Ok(());
+            }
+        };
+
+        for (server_name, instance_name) in decode_instances(&buf[..len]) {
+            if seen.insert((from, instance_name.clone())) {
+                let instance = DiscoveredInstance { addr: from, server_name, instance_name };
+                if tx.send(Ok(instance)).await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Decodes the `ServerName`/`InstanceName` pairs out of a `SVR_RESP`
+/// broadcast reply, which lists every instance on the responding
+/// machine as a run of semicolon-delimited key/value pairs, with each
+/// instance's record terminated by `;;`.
+fn decode_instances(datagram: &[u8]) -> Vec<(String, String)> {
+    let Ok(text) = std::str::from_utf8(datagram.get(3..).unwrap_or(&[])) else {
+        return Vec::new();
+    };
+
+    text.split(";;")
+        .filter_map(|record| {
+            let mut fields = record.split(';');
+            let mut server_name = None;
+            let mut instance_name = None;
+
+            while let (Some(key), Some(value)) = (fields.next(), fields.next()) {
+                match key {
+                    "ServerName" => server_name = Some(value.to_string()),
+                    "InstanceName" => instance_name = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+
+            Some((server_name?, instance_name?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockSocket;
+    use futures::StreamExt;
+
+    fn svr_resp(server_name: &str, instance_name: &str) -> Vec<u8> {
+        let mut datagram = vec![0x05, 0x00, 0x00];
+        datagram.extend_from_slice(format!("ServerName;{};InstanceName;{};;", server_name, instance_name).as_bytes());
+        datagram
+    }
+
+    #[tokio::test]
+    async fn discover_with_dedups_by_source_addr_and_instance_name() {
+        let addr_a = SocketAddr::from(([10, 0, 0, 1], 1434));
+        let addr_b = SocketAddr::from(([10, 0, 0, 2], 1434));
+        let broadcast_addr = SocketAddr::from(([255, 255, 255, 255], 1434));
+
+        let socket = MockSocket::from_sources(vec![
+            (addr_a, svr_resp("HOST1", "SQLEXPRESS")),
+            (addr_a, svr_resp("HOST1", "SQLEXPRESS")),
+            (addr_b, svr_resp("HOST2", "MSSQLSERVER"))
+        ]);
+
+        let instances: Vec<_> = discover_with::<MockSocket>(socket, broadcast_addr, Duration::from_millis(20))
+            .filter_map(|result| async move { result.ok() })
+            .collect()
+            .await;
+
+        assert_eq!(instances, vec![
+            DiscoveredInstance { addr: addr_a, server_name: "HOST1".to_string(), instance_name: "SQLEXPRESS".to_string() },
+            DiscoveredInstance { addr: addr_b, server_name: "HOST2".to_string(), instance_name: "MSSQLSERVER".to_string() }
+        ]);
+    }
+
+    #[test]
+    fn decode_instances_parses_server_and_instance_name_pairs() {
+        let datagram = svr_resp("HOST1", "SQLEXPRESS");
+
+        assert_eq!(decode_instances(&datagram), vec![("HOST1".to_string(), "SQLEXPRESS".to_string())]);
+    }
+}
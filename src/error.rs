@@ -1,5 +1,6 @@
 use std::net::{ SocketAddr };
 use std::error::Error;
+use std::time::Duration;
 
 /// An error that can be returned from the different browser operations
 #[derive(Debug)]
@@ -22,6 +23,20 @@ pub enum BrowserError<SocketError: std::error::Error> {
     /// The given instance name is too long.
     InstanceNameTooLong,
 
+    /// No response was received before the configured deadline, after
+    /// exhausting the configured number of retries.
+    Timeout {
+        /// How long was spent waiting across all attempts.
+        elapsed: Duration,
+
+        /// How many times the request was sent, including the initial
+        /// attempt.
+        attempts: u32
+    },
+
+    /// Resolving the given host produced no usable addresses.
+    ResolutionFailed,
+
     /// The server send back an invalid response.
     ProtocolError(BrowserProtocolError)
 }
@@ -37,6 +52,8 @@ impl<SocketError: Error> std::fmt::Display for BrowserError<SocketError> {
             ConnectFailed(addr, err) => write!(f, "connect to '{}' failed: {}", addr, err),
             ReceiveFailed(err) => write!(f, "receiving of datagram failed: {}", err),
             InstanceNameTooLong => write!(f, "specified instance name is longer than {} bytes", super::MAX_INSTANCE_NAME_LEN),
+            Timeout { elapsed, attempts } => write!(f, "no response received after {} attempt(s) ({:.1}s elapsed)", attempts, elapsed.as_secs_f32()),
+            ResolutionFailed => write!(f, "resolving the host produced no usable addresses"),
             ProtocolError(e) => write!(f, "protocol error: {}", e)
         }
     }
@@ -53,6 +70,8 @@ impl<SocketError: Error> Error for BrowserError<SocketError> {
             ConnectFailed(_, err) => Some(err),
             ReceiveFailed(err) => Some(err),
             InstanceNameTooLong => None,
+            Timeout { .. } => None,
+            ResolutionFailed => None,
             ProtocolError(err) => Some(err)
         }
     }
@@ -67,7 +86,13 @@ pub enum BrowserProtocolError {
         expected: BrowserProtocolToken,
 
         /// The token that was found
-        found: BrowserProtocolToken
+        found: BrowserProtocolToken,
+
+        /// The byte offset, within the datagram, where the mismatch was found
+        offset: usize,
+
+        /// A bounded hex dump of the datagram around `offset`
+        context: HexSnippet
     },
 
     /// The length of the datagram does not match the length
@@ -77,11 +102,26 @@ pub enum BrowserProtocolError {
         datagram: usize,
 
         /// The size, in bytes, specified in the packet header
-        header: usize
+        header: usize,
+
+        /// The byte offset, within the datagram, of the length field
+        offset: usize,
+
+        /// A bounded hex dump of the datagram around `offset`
+        context: HexSnippet
     },
 
     /// Unexpected MBCS string encoding found in the received message
-    InvalidUtf8(std::str::Utf8Error),
+    InvalidUtf8 {
+        /// The underlying UTF-8 validation error
+        source: std::str::Utf8Error,
+
+        /// The byte offset, within the datagram, where the invalid string starts
+        offset: usize,
+
+        /// A bounded hex dump of the datagram around `offset`
+        context: HexSnippet
+    },
 
     /// There was extraneous data after the parsed message
     ExtraneousData(Vec<u8>)
@@ -92,12 +132,12 @@ impl std::fmt::Display for BrowserProtocolError {
         use BrowserProtocolError::*;
 
         match self {
-            UnexpectedToken { expected, found } 
-                => write!(f, "expected {}, but found {}", expected, found),
-            LengthMismatch { datagram, header }
-                => write!(f, "mismatch between datagram size {} bytes and size specified in header {} bytes", datagram, header),
-            InvalidUtf8(err)
-                => err.fmt(f),
+            UnexpectedToken { expected, found, offset, context }
+                => write!(f, "expected {} at byte {}, but found {}: {}", expected, offset, found, context),
+            LengthMismatch { datagram, header, offset, context }
+                => write!(f, "mismatch between datagram size {} bytes and size specified in header {} bytes at byte {}: {}", datagram, header, offset, context),
+            InvalidUtf8 { source, offset, context }
+                => write!(f, "{} at byte {}: {}", source, offset, context),
             ExtraneousData(data)
                 => write!(f, "{} unexpected trailing bytes", data.len())
         }
@@ -106,6 +146,40 @@ impl std::fmt::Display for BrowserProtocolError {
 
 impl Error for BrowserProtocolError { }
 
+/// A bounded window of raw datagram bytes surrounding the location of a
+/// protocol error, rendered as a hex dump for diagnostics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexSnippet(Vec<u8>);
+
+impl HexSnippet {
+    /// How many bytes of context to keep on each side of the offset of
+    /// interest.
+    const RADIUS: usize = 8;
+
+    /// Captures a bounded window of `datagram` centered on `offset`.
+    ///
+    /// `offset` may come from an untrusted, malformed datagram and can
+    /// exceed `datagram.len()`; in that case the window is clamped to
+    /// the end of the datagram rather than panicking.
+    pub fn around(datagram: &[u8], offset: usize) -> Self {
+        let end = (offset + Self::RADIUS).min(datagram.len());
+        let start = offset.saturating_sub(Self::RADIUS).min(end);
+        Self(datagram[start..end].to_vec())
+    }
+}
+
+impl std::fmt::Display for HexSnippet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
 /// The value that was expected.
 #[derive(Debug)]
 pub enum BrowserProtocolToken {
@@ -167,4 +241,66 @@ pub enum BrowserProtocolField {
     BvItemName,
     BvGroupName,
     BvOrgName
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_snippet_around_is_centered_on_offset() {
+        let datagram: Vec<u8> = (0..32).collect();
+
+        let snippet = HexSnippet::around(&datagram, 16);
+
+        assert_eq!(snippet.0, datagram[8..24]);
+    }
+
+    #[test]
+    fn hex_snippet_around_does_not_panic_past_the_end_of_the_datagram() {
+        let datagram = [0x05, 0x00, 0x10];
+
+        let snippet = HexSnippet::around(&datagram, 1_000);
+
+        assert_eq!(snippet.0, Vec::<u8>::new());
+    }
+
+    #[test]
+    fn unexpected_token_display_includes_offset_and_hex_dump() {
+        let datagram = [0x05, 0x00, 0x10, 0xAB, 0xCD];
+        let err = BrowserProtocolError::UnexpectedToken {
+            expected: BrowserProtocolToken::TcpPort,
+            found: BrowserProtocolToken::DacPort,
+            offset: 3,
+            context: HexSnippet::around(&datagram, 3)
+        };
+
+        assert_eq!(err.to_string(), "expected tcp port at byte 3, but found dac port: 05 00 10 ab cd");
+    }
+
+    #[test]
+    fn length_mismatch_display_includes_offset_and_hex_dump() {
+        let datagram = [0x05, 0x2A, 0x00];
+        let err = BrowserProtocolError::LengthMismatch {
+            datagram: 3,
+            header: 42,
+            offset: 1,
+            context: HexSnippet::around(&datagram, 1)
+        };
+
+        assert_eq!(err.to_string(), "mismatch between datagram size 3 bytes and size specified in header 42 bytes at byte 1: 05 2a 00");
+    }
+
+    #[test]
+    fn invalid_utf8_display_includes_offset_and_hex_dump() {
+        let datagram = [0x05, 0xFF, 0xFE];
+        let source = std::str::from_utf8(&datagram[1..]).unwrap_err();
+        let err = BrowserProtocolError::InvalidUtf8 {
+            source,
+            offset: 1,
+            context: HexSnippet::around(&datagram, 1)
+        };
+
+        assert_eq!(err.to_string(), format!("{} at byte 1: 05 ff fe", source));
+    }
 }
\ No newline at end of file
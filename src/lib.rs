@@ -0,0 +1,25 @@
+//! A client for the Microsoft SQL Server Browser Service (SSRP), the UDP
+//! discovery protocol SQL Server clients use to find named instances and
+//! their TCP ports on a network.
+
+mod config;
+mod discovery;
+mod error;
+pub mod mock;
+mod query;
+mod resolve;
+mod socket;
+
+pub use config::QueryConfig;
+pub use discovery::{ discover, discover_with, DiscoveredInstance };
+pub use error::{ BrowserError, BrowserProtocolError, BrowserProtocolField, BrowserProtocolToken };
+pub use query::{ query_broadcast, query_broadcast_with, query_unicast, query_unicast_with };
+pub use resolve::query_unicast_host;
+pub use socket::BrowserSocket;
+
+/// The maximum length, in bytes, of an instance name accepted by the
+/// SSRP protocol.
+pub const MAX_INSTANCE_NAME_LEN: usize = 32;
+
+/// The default UDP port the SQL Server Browser service listens on.
+pub const DEFAULT_BROWSER_PORT: u16 = 1434;
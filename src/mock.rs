@@ -0,0 +1,102 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+
+use crate::socket::BrowserSocket;
+
+/// A placeholder source address used by [`MockSocket::new`] when the
+/// caller doesn't care which peer a canned reply came from.
+const UNSPECIFIED_SOURCE: SocketAddr = SocketAddr::new(std::net::IpAddr::V4(std::net::Ipv4Addr::UNSPECIFIED), 0);
+
+/// An in-memory [`BrowserSocket`] that replays a fixed sequence of
+/// canned datagrams instead of talking to a real network.
+///
+/// Useful for exercising the protocol parser's error paths (malformed
+/// lengths, unexpected tokens, ...) without standing up a server.
+pub struct MockSocket {
+    replies: Mutex<VecDeque<(SocketAddr, Vec<u8>)>>,
+    sent: Mutex<Vec<(Vec<u8>, SocketAddr)>>
+}
+
+impl MockSocket {
+    /// Creates a mock socket that will hand back `replies`, in order, to
+    /// successive calls to `recv`, as if they all came from the same
+    /// unspecified peer.
+    pub fn new(replies: impl IntoIterator<Item = Vec<u8>>) -> Self {
+        Self::from_sources(replies.into_iter().map(|reply| (UNSPECIFIED_SOURCE, reply)))
+    }
+
+    /// Creates a mock socket that will hand back `replies`, in order, as
+    /// if each one arrived from its paired source address. Useful for
+    /// testing broadcast discovery, where replies come from many peers.
+    pub fn from_sources(replies: impl IntoIterator<Item = (SocketAddr, Vec<u8>)>) -> Self {
+        Self {
+            replies: Mutex::new(replies.into_iter().collect()),
+            sent: Mutex::new(Vec::new())
+        }
+    }
+
+    /// The datagrams sent through this socket so far, in order.
+    pub fn sent(&self) -> Vec<(Vec<u8>, SocketAddr)> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+/// The only way a [`MockSocket`] can fail: its queue of canned replies
+/// was empty when `recv`/`recv_from` was called.
+#[derive(Debug)]
+pub struct MockSocketExhausted;
+
+impl fmt::Display for MockSocketExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "mock socket has no more canned replies queued")
+    }
+}
+
+impl std::error::Error for MockSocketExhausted {}
+
+impl BrowserSocket for MockSocket {
+    type Error = MockSocketExhausted;
+
+    /// Binding a `MockSocket` always starts an empty one with no canned
+    /// replies queued. To exercise `query_unicast`/`query_broadcast`/
+    /// `discover` against canned data, build a `MockSocket` with
+    /// [`MockSocket::new`]/[`MockSocket::from_sources`] and pass it to
+    /// their `_with` counterparts instead of binding through `S::bind`.
+    fn bind(_addr: SocketAddr) -> impl Future<Output = Result<Self, Self::Error>> + Send {
+        async { Ok(Self::new(Vec::new())) }
+    }
+
+    fn set_broadcast(&self, _enabled: bool) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn send_to<'a>(&'a self, buf: &'a [u8], addr: SocketAddr) -> impl Future<Output = Result<usize, Self::Error>> + Send + 'a {
+        async move {
+            self.sent.lock().unwrap().push((buf.to_vec(), addr));
+            Ok(buf.len())
+        }
+    }
+
+    fn connect<'a>(&'a self, _addr: SocketAddr) -> impl Future<Output = Result<(), Self::Error>> + Send + 'a {
+        async { Ok(()) }
+    }
+
+    fn recv<'a>(&'a self, buf: &'a mut [u8]) -> impl Future<Output = Result<usize, Self::Error>> + Send + 'a {
+        async move {
+            let (len, _from) = self.recv_from(buf).await?;
+            Ok(len)
+        }
+    }
+
+    fn recv_from<'a>(&'a self, buf: &'a mut [u8]) -> impl Future<Output = Result<(usize, SocketAddr), Self::Error>> + Send + 'a {
+        async move {
+            let (from, reply) = self.replies.lock().unwrap().pop_front().ok_or(MockSocketExhausted)?;
+            let len = reply.len().min(buf.len());
+            buf[..len].copy_from_slice(&reply[..len]);
+            Ok((len, from))
+        }
+    }
+}
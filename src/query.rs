@@ -0,0 +1,233 @@
+use std::net::SocketAddr;
+use std::time::Instant;
+
+use tokio::time::timeout;
+
+use crate::config::QueryConfig;
+use crate::error::BrowserError;
+use crate::socket::BrowserSocket;
+use crate::MAX_INSTANCE_NAME_LEN;
+
+/// Sends a `CLNT_UCAST_EX` request to `addr` (or `CLNT_UCAST_INST` when
+/// `instance_name` is given) and returns the raw `SVR_RESP` datagram.
+///
+/// Because SSRP is carried over unreliable UDP, the request is re-sent
+/// up to `config.retries` additional times if no response arrives
+/// within `config.timeout`, before a `BrowserError::Timeout` is
+/// returned.
+///
+/// Binds a fresh `S` for the query; use [`query_unicast_with`] to reuse
+/// an existing socket, e.g. a pre-loaded `MockSocket` in tests.
+pub async fn query_unicast<S: BrowserSocket>(
+    addr: SocketAddr,
+    instance_name: Option<&str>,
+    config: &QueryConfig
+) -> Result<Vec<u8>, BrowserError<S::Error>> {
+    let socket = S::bind(unspecified_addr(addr)).await.map_err(BrowserError::BindFailed)?;
+
+    query_unicast_with(&socket, addr, instance_name, config).await
+}
+
+/// Like [`query_unicast`], but against an already-constructed `socket`
+/// instead of binding a new one.
+pub async fn query_unicast_with<S: BrowserSocket>(
+    socket: &S,
+    addr: SocketAddr,
+    instance_name: Option<&str>,
+    config: &QueryConfig
+) -> Result<Vec<u8>, BrowserError<S::Error>> {
+    let request = build_unicast_request(instance_name)?;
+
+    socket.connect(addr).await.map_err(|e| BrowserError::ConnectFailed(addr, e))?;
+
+    send_and_receive(socket, addr, &request, config).await
+}
+
+/// Sends a `CLNT_BCAST_EX` request to `addr` (typically a subnet
+/// broadcast address on port 1434) and returns the raw datagram of the
+/// first reply received.
+///
+/// See [`query_unicast`] for the retry behaviour; a broadcast query
+/// only ever resolves on the first reply, it does not wait for further
+/// instances to answer.
+///
+/// Binds a fresh `S` for the query; use [`query_broadcast_with`] to
+/// reuse an existing socket, e.g. a pre-loaded `MockSocket` in tests.
+pub async fn query_broadcast<S: BrowserSocket>(
+    addr: SocketAddr,
+    config: &QueryConfig
+) -> Result<Vec<u8>, BrowserError<S::Error>> {
+    let socket = S::bind(unspecified_addr(addr)).await.map_err(BrowserError::BindFailed)?;
+
+    query_broadcast_with(&socket, addr, config).await
+}
+
+/// Like [`query_broadcast`], but against an already-constructed
+/// `socket` instead of binding a new one.
+pub async fn query_broadcast_with<S: BrowserSocket>(
+    socket: &S,
+    addr: SocketAddr,
+    config: &QueryConfig
+) -> Result<Vec<u8>, BrowserError<S::Error>> {
+    let request = [0x02];
+
+    socket.set_broadcast(true).map_err(BrowserError::SetBroadcastFailed)?;
+
+    send_and_receive(socket, addr, &request, config).await
+}
+
+async fn send_and_receive<S: BrowserSocket>(
+    socket: &S,
+    addr: SocketAddr,
+    request: &[u8],
+    config: &QueryConfig
+) -> Result<Vec<u8>, BrowserError<S::Error>> {
+    let started = Instant::now();
+    let mut buf = [0u8; 4096];
+
+    for attempt in 0..config.attempts() {
+        socket.send_to(request, addr).await.map_err(|e| BrowserError::SendFailed(addr, e))?;
+
+        match timeout(config.timeout, socket.recv(&mut buf)).await {
+            Ok(Ok(len)) => return Ok(buf[..len].to_vec()),
+            Ok(Err(err)) => return Err(BrowserError::ReceiveFailed(err)),
+            Err(_elapsed) if attempt + 1 < config.attempts() => continue,
+            Err(_elapsed) => return Err(BrowserError::Timeout {
+                elapsed: started.elapsed(),
+                attempts: config.attempts()
+            })
+        }
+    }
+
+    unreachable!("config.attempts() is always at least 1")
+}
+
+fn build_unicast_request<E: std::error::Error>(instance_name: Option<&str>) -> Result<Vec<u8>, BrowserError<E>> {
+    match instance_name {
+        None => Ok(vec![0x03]),
+        Some(name) => {
+            if name.len() > MAX_INSTANCE_NAME_LEN {
+                return Err(BrowserError::InstanceNameTooLong);
+            }
+
+            let mut request = vec![0x04];
+            request.extend_from_slice(name.as_bytes());
+            Ok(request)
+        }
+    }
+}
+
+/// The local address to bind to before talking to `peer`: all zeroes,
+/// on the same address family as `peer`, so the OS picks a free port.
+fn unspecified_addr(peer: SocketAddr) -> SocketAddr {
+    match peer {
+        SocketAddr::V4(_) => SocketAddr::from(([0, 0, 0, 0], 0)),
+        SocketAddr::V6(_) => SocketAddr::from(([0u16; 8], 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockSocket;
+    use std::time::Duration;
+
+    fn addr() -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], 1434))
+    }
+
+    /// A `BrowserSocket` that never replies, so `recv`/`recv_from` always
+    /// time out. Used to exercise the retry-then-`Timeout` path, which a
+    /// `MockSocket` (whose `recv` resolves immediately) can't reach.
+    struct NeverRespondingSocket;
+
+    impl BrowserSocket for NeverRespondingSocket {
+        type Error = std::io::Error;
+
+        fn bind(_addr: SocketAddr) -> impl std::future::Future<Output = Result<Self, Self::Error>> + Send {
+            async { Ok(Self) }
+        }
+
+        fn set_broadcast(&self, _enabled: bool) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn send_to<'a>(&'a self, buf: &'a [u8], _addr: SocketAddr) -> impl std::future::Future<Output = Result<usize, Self::Error>> + Send + 'a {
+            async move { Ok(buf.len()) }
+        }
+
+        fn connect<'a>(&'a self, _addr: SocketAddr) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send + 'a {
+            async { Ok(()) }
+        }
+
+        fn recv<'a>(&'a self, _buf: &'a mut [u8]) -> impl std::future::Future<Output = Result<usize, Self::Error>> + Send + 'a {
+            std::future::pending()
+        }
+
+        fn recv_from<'a>(&'a self, _buf: &'a mut [u8]) -> impl std::future::Future<Output = Result<(usize, SocketAddr), Self::Error>> + Send + 'a {
+            std::future::pending()
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn query_unicast_with_returns_timeout_after_exhausting_retries() {
+        let socket = NeverRespondingSocket;
+        let config = QueryConfig::new(Duration::from_millis(50), 2);
+
+        let err = query_unicast_with(&socket, addr(), None, &config).await.unwrap_err();
+
+        match err {
+            BrowserError::Timeout { attempts, .. } => assert_eq!(attempts, config.attempts()),
+            other => panic!("expected BrowserError::Timeout, got {other:?}")
+        }
+    }
+
+    #[tokio::test]
+    async fn query_unicast_with_returns_the_canned_reply() {
+        let socket = MockSocket::new(vec![vec![0x05, 0x01, 0x02, 0x03]]);
+
+        let response = query_unicast_with(&socket, addr(), None, &QueryConfig::default()).await.unwrap();
+
+        assert_eq!(response, vec![0x05, 0x01, 0x02, 0x03]);
+        assert_eq!(socket.sent(), vec![(vec![0x03], addr())]);
+    }
+
+    #[tokio::test]
+    async fn query_unicast_with_instance_name_sends_clnt_ucast_inst() {
+        let socket = MockSocket::new(vec![vec![0x05]]);
+
+        query_unicast_with(&socket, addr(), Some("SQLEXPRESS"), &QueryConfig::default()).await.unwrap();
+
+        let mut expected_request = vec![0x04];
+        expected_request.extend_from_slice(b"SQLEXPRESS");
+        assert_eq!(socket.sent(), vec![(expected_request, addr())]);
+    }
+
+    #[tokio::test]
+    async fn query_unicast_with_surfaces_receive_errors_once_replies_are_exhausted() {
+        let socket = MockSocket::new(Vec::new());
+
+        let err = query_unicast_with(&socket, addr(), None, &QueryConfig::default()).await.unwrap_err();
+
+        assert!(matches!(err, BrowserError::ReceiveFailed(_)));
+    }
+
+    #[tokio::test]
+    async fn query_broadcast_with_returns_the_canned_reply() {
+        let socket = MockSocket::new(vec![vec![0x05, 0xAA]]);
+
+        let response = query_broadcast_with(&socket, addr(), &QueryConfig::default()).await.unwrap();
+
+        assert_eq!(response, vec![0x05, 0xAA]);
+        assert_eq!(socket.sent(), vec![(vec![0x02], addr())]);
+    }
+
+    #[test]
+    fn instance_name_too_long_is_rejected_before_any_io() {
+        let name = "x".repeat(MAX_INSTANCE_NAME_LEN + 1);
+
+        let err = build_unicast_request::<std::io::Error>(Some(&name)).unwrap_err();
+
+        assert!(matches!(err, BrowserError::InstanceNameTooLong));
+    }
+}
@@ -0,0 +1,110 @@
+use std::net::SocketAddr;
+
+use crate::config::QueryConfig;
+use crate::error::BrowserError;
+use crate::query::query_unicast;
+use crate::socket::BrowserSocket;
+use crate::DEFAULT_BROWSER_PORT;
+
+/// Resolves `host` and issues a unicast query to each resolved address
+/// in turn, returning the first successful response.
+///
+/// `host` may be a plain hostname (in which case [`DEFAULT_BROWSER_PORT`]
+/// is assumed), or a `host:port` pair. This mirrors how HTTP/RPC clients
+/// resolve a host and try candidate addresses instead of requiring a
+/// pre-resolved `SocketAddr`.
+///
+/// If resolution yields no addresses at all, `BrowserError::ResolutionFailed`
+/// is returned. Otherwise, each resolved address is tried in turn; if
+/// every one of them fails, the error from the last address tried is
+/// returned.
+pub async fn query_unicast_host<S: BrowserSocket>(
+    host: &str,
+    instance_name: Option<&str>,
+    config: &QueryConfig
+) -> Result<Vec<u8>, BrowserError<S::Error>> {
+    let addrs = resolve(host).await;
+
+    let mut last_err = None;
+
+    for addr in addrs {
+        match query_unicast::<S>(addr, instance_name, config).await {
+            Ok(response) => return Ok(response),
+            Err(err) => last_err = Some(err)
+        }
+    }
+
+    match last_err {
+        Some(err) => Err(err),
+        None => Err(BrowserError::ResolutionFailed)
+    }
+}
+
+/// Resolves `host` to zero or more addresses, assuming
+/// `DEFAULT_BROWSER_PORT` when `host` does not already specify a port.
+async fn resolve(host: &str) -> Vec<SocketAddr> {
+    let target = if has_explicit_port(host) {
+        host.to_string()
+    } else if host.contains(':') && !host.starts_with('[') {
+        // A bare IPv6 literal, e.g. "::1" or "2001:db8::1": bracket it
+        // before appending a port, since "host:port" parsing would
+        // otherwise misread one of its own colons as the port separator.
+        format!("[{}]:{}", host, DEFAULT_BROWSER_PORT)
+    } else {
+        format!("{}:{}", host, DEFAULT_BROWSER_PORT)
+    };
+
+    tokio::net::lookup_host(target).await
+        .map(|addrs| addrs.collect())
+        .unwrap_or_default()
+}
+
+/// Whether `host` already specifies a port, as opposed to being a bare
+/// hostname or IP address.
+///
+/// Bare (unbracketed) IPv6 literals such as `::1` contain more than one
+/// colon, so a single trailing `:<digits>` only counts as a port when
+/// `host` is bracketed (`[::1]:1433`) or has exactly one colon
+/// (`host:1433`, `1.2.3.4:1433`).
+fn has_explicit_port(host: &str) -> bool {
+    if let Some(rest) = host.strip_prefix('[') {
+        return rest.split_once(']').is_some_and(|(_, suffix)| suffix.starts_with(':'));
+    }
+
+    if host.matches(':').count() > 1 {
+        return false;
+    }
+
+    host.rsplit_once(':').is_some_and(|(_, port)| port.parse::<u16>().is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::has_explicit_port;
+
+    #[test]
+    fn bare_hostname_has_no_port() {
+        assert!(!has_explicit_port("sqlserver.example.com"));
+    }
+
+    #[test]
+    fn hostname_with_port_is_detected() {
+        assert!(has_explicit_port("sqlserver.example.com:1433"));
+    }
+
+    #[test]
+    fn bare_ipv6_literal_has_no_port() {
+        assert!(!has_explicit_port("::1"));
+        assert!(!has_explicit_port("2001:db8::1"));
+    }
+
+    #[test]
+    fn bracketed_ipv6_with_port_is_detected() {
+        assert!(has_explicit_port("[::1]:1433"));
+    }
+
+    #[test]
+    fn bracketed_ipv6_without_port_has_no_port() {
+        assert!(!has_explicit_port("[::1]"));
+    }
+}
@@ -0,0 +1,90 @@
+use std::future::Future;
+use std::net::SocketAddr;
+
+/// The socket operations the SSRP client needs.
+///
+/// This only abstracts the datagram transport, not timers, spawning, or
+/// DNS resolution — `query`, `discovery`, and `resolve` still call into
+/// `tokio` directly for those, so the crate as a whole remains tied to
+/// the tokio runtime. What this buys is the ability to substitute an
+/// in-memory [`mock::MockSocket`](crate::mock::MockSocket) that replays
+/// canned datagrams, for testing the protocol layer without a real
+/// network.
+///
+/// Methods return `impl Future + Send` rather than being declared
+/// `async fn`, so the `Send` bound on the returned future is part of
+/// the trait's contract instead of being left for callers to hope for;
+/// `discovery::discover` relies on that bound to spawn the query onto
+/// the runtime.
+pub trait BrowserSocket: Sized {
+    /// The error type returned by this socket's operations; flows into
+    /// `BrowserError`'s `SocketError` parameter.
+    type Error: std::error::Error;
+
+    /// Binds a new socket to `addr`.
+    fn bind(addr: SocketAddr) -> impl Future<Output = Result<Self, Self::Error>> + Send;
+
+    /// Enables the broadcast option, allowing datagrams to be sent to
+    /// broadcast addresses.
+    fn set_broadcast(&self, enabled: bool) -> Result<(), Self::Error>;
+
+    /// Sends `buf` to `addr`.
+    fn send_to<'a>(&'a self, buf: &'a [u8], addr: SocketAddr) -> impl Future<Output = Result<usize, Self::Error>> + Send + 'a;
+
+    /// Connects the socket to `addr`, so that `recv` only accepts
+    /// datagrams from that peer.
+    fn connect<'a>(&'a self, addr: SocketAddr) -> impl Future<Output = Result<(), Self::Error>> + Send + 'a;
+
+    /// Receives a datagram into `buf`, returning the number of bytes
+    /// written.
+    fn recv<'a>(&'a self, buf: &'a mut [u8]) -> impl Future<Output = Result<usize, Self::Error>> + Send + 'a;
+
+    /// Receives a datagram into `buf` without requiring the socket to
+    /// be connected to a single peer, returning the number of bytes
+    /// written and the address the datagram was sent from.
+    ///
+    /// Used for broadcast discovery, where replies arrive from many
+    /// different peers.
+    fn recv_from<'a>(&'a self, buf: &'a mut [u8]) -> impl Future<Output = Result<(usize, SocketAddr), Self::Error>> + Send + 'a;
+}
+
+/// tokio-backed implementation of [`BrowserSocket`].
+///
+/// Not feature-gated: the rest of the crate already calls `tokio`
+/// directly for timers, task spawning, and DNS resolution, so gating
+/// only this impl behind a feature flag would misrepresent the crate
+/// as runtime-agnostic when it isn't.
+mod tokio_impl {
+    use super::BrowserSocket;
+    use std::future::Future;
+    use std::net::SocketAddr;
+    use tokio::net::UdpSocket;
+
+    impl BrowserSocket for UdpSocket {
+        type Error = std::io::Error;
+
+        fn bind(addr: SocketAddr) -> impl Future<Output = Result<Self, Self::Error>> + Send {
+            UdpSocket::bind(addr)
+        }
+
+        fn set_broadcast(&self, enabled: bool) -> Result<(), Self::Error> {
+            UdpSocket::set_broadcast(self, enabled)
+        }
+
+        fn send_to<'a>(&'a self, buf: &'a [u8], addr: SocketAddr) -> impl Future<Output = Result<usize, Self::Error>> + Send + 'a {
+            UdpSocket::send_to(self, buf, addr)
+        }
+
+        fn connect<'a>(&'a self, addr: SocketAddr) -> impl Future<Output = Result<(), Self::Error>> + Send + 'a {
+            UdpSocket::connect(self, addr)
+        }
+
+        fn recv<'a>(&'a self, buf: &'a mut [u8]) -> impl Future<Output = Result<usize, Self::Error>> + Send + 'a {
+            UdpSocket::recv(self, buf)
+        }
+
+        fn recv_from<'a>(&'a self, buf: &'a mut [u8]) -> impl Future<Output = Result<(usize, SocketAddr), Self::Error>> + Send + 'a {
+            UdpSocket::recv_from(self, buf)
+        }
+    }
+}